@@ -0,0 +1,301 @@
+// fed_crypto/src/codec.rs
+//
+// Versioned, length-prefixed binary wire format for the two message types
+// this crate hands between federated participants: a compressed-gradient
+// update (see `compressor`) and a Shamir secret share (see `shamir`). Both
+// decoders validate lengths explicitly and return `Err` on truncated or
+// over-long input rather than panicking or indexing out of bounds.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+const MAGIC_GRADIENT: u8 = 0xC6;
+const MAGIC_SHARE: u8 = 0xC7;
+const VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum CodecError {
+    Truncated,
+    TrailingBytes,
+    BadMagic,
+    UnsupportedVersion(u8),
+    IndexOutOfRange { index: u64, original_len: u64 },
+    IndexOverflow,
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::Truncated => write!(f, "buffer truncated"),
+            CodecError::TrailingBytes => write!(f, "buffer has unexpected trailing bytes"),
+            CodecError::BadMagic => write!(f, "bad magic byte"),
+            CodecError::UnsupportedVersion(v) => write!(f, "unsupported wire format version {}", v),
+            CodecError::IndexOutOfRange { index, original_len } => {
+                write!(f, "index {} out of range for original_len {}", index, original_len)
+            }
+            CodecError::IndexOverflow => write!(f, "delta-encoded index overflowed u64"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl From<CodecError> for PyErr {
+    fn from(err: CodecError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Result<u64, CodecError> {
+    let mut result: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let byte = *buf.get(*pos).ok_or(CodecError::Truncated)?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+        if shift >= 64 {
+            return Err(CodecError::Truncated);
+        }
+    }
+}
+
+/// Encodes a compressed-gradient message: `original_len`, the sorted,
+/// delta-encoded `indices`, and the kept `float32` `values`.
+pub fn encode_compressed_gradient(original_len: u64, indices: &[u64], values: &[f32]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(2 + 10 + 10 + indices.len() * 2 + values.len() * 4);
+    buf.push(MAGIC_GRADIENT);
+    buf.push(VERSION);
+    write_varint(&mut buf, original_len);
+    write_varint(&mut buf, indices.len() as u64);
+
+    let mut prev = 0u64;
+    for &idx in indices {
+        write_varint(&mut buf, idx - prev);
+        prev = idx;
+    }
+    for &v in values {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+    buf
+}
+
+/// Decodes a compressed-gradient message produced by `encode_compressed_gradient`.
+pub fn decode_compressed_gradient(buf: &[u8]) -> Result<(u64, Vec<u64>, Vec<f32>), CodecError> {
+    let mut pos = 0usize;
+    if buf.len() < 2 {
+        return Err(CodecError::Truncated);
+    }
+    if buf[0] != MAGIC_GRADIENT {
+        return Err(CodecError::BadMagic);
+    }
+    if buf[1] != VERSION {
+        return Err(CodecError::UnsupportedVersion(buf[1]));
+    }
+    pos += 2;
+
+    let original_len = read_varint(buf, &mut pos)?;
+    let k = read_varint(buf, &mut pos)?;
+
+    // `k` comes straight off the wire and is otherwise unbounded; every index
+    // takes at least one byte and every value four, so reject before
+    // allocating instead of trusting it for `Vec::with_capacity`.
+    if k > buf.len() as u64 {
+        return Err(CodecError::Truncated);
+    }
+
+    let mut indices = Vec::with_capacity(k as usize);
+    let mut prev = 0u64;
+    for _ in 0..k {
+        let delta = read_varint(buf, &mut pos)?;
+        let idx = prev.checked_add(delta).ok_or(CodecError::IndexOverflow)?;
+        if idx >= original_len {
+            return Err(CodecError::IndexOutOfRange { index: idx, original_len });
+        }
+        indices.push(idx);
+        prev = idx;
+    }
+
+    let values_bytes = k as usize * 4;
+    let remaining = buf.len() - pos;
+    if remaining < values_bytes {
+        return Err(CodecError::Truncated);
+    }
+    if remaining > values_bytes {
+        return Err(CodecError::TrailingBytes);
+    }
+
+    let mut values = Vec::with_capacity(k as usize);
+    for _ in 0..k {
+        let chunk: [u8; 4] = buf[pos..pos + 4].try_into().map_err(|_| CodecError::Truncated)?;
+        values.push(f32::from_le_bytes(chunk));
+        pos += 4;
+    }
+
+    Ok((original_len, indices, values))
+}
+
+/// Encodes a secret-share message: the evaluation point `x` (varint) and the
+/// field element `y` as fixed-width, 16-byte big-endian bytes (the width of
+/// an `Fp127` element, see `fp127`).
+pub fn encode_secret_share(x: u64, y: u128) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(2 + 10 + 16);
+    buf.push(MAGIC_SHARE);
+    buf.push(VERSION);
+    write_varint(&mut buf, x);
+    buf.extend_from_slice(&y.to_be_bytes());
+    buf
+}
+
+/// Decodes a secret-share message produced by `encode_secret_share`.
+pub fn decode_secret_share(buf: &[u8]) -> Result<(u64, u128), CodecError> {
+    let mut pos = 0usize;
+    if buf.len() < 2 {
+        return Err(CodecError::Truncated);
+    }
+    if buf[0] != MAGIC_SHARE {
+        return Err(CodecError::BadMagic);
+    }
+    if buf[1] != VERSION {
+        return Err(CodecError::UnsupportedVersion(buf[1]));
+    }
+    pos += 2;
+
+    let x = read_varint(buf, &mut pos)?;
+
+    let remaining = buf.len() - pos;
+    if remaining < 16 {
+        return Err(CodecError::Truncated);
+    }
+    if remaining > 16 {
+        return Err(CodecError::TrailingBytes);
+    }
+    let y_bytes: [u8; 16] = buf[pos..pos + 16].try_into().map_err(|_| CodecError::Truncated)?;
+    let y = u128::from_be_bytes(y_bytes);
+
+    Ok((x, y))
+}
+
+#[pyfunction]
+pub fn encode_compressed_gradient_py(
+    py: Python,
+    original_len: u64,
+    indices: Vec<u64>,
+    values: Vec<f32>,
+) -> PyResult<PyObject> {
+    let buf = encode_compressed_gradient(original_len, &indices, &values);
+    Ok(PyBytes::new(py, &buf).to_object(py))
+}
+
+#[pyfunction]
+pub fn decode_compressed_gradient_py(py: Python, buf: &[u8]) -> PyResult<(u64, PyObject, PyObject)> {
+    let (original_len, indices, values) = decode_compressed_gradient(buf)?;
+    Ok((original_len, indices.to_object(py), values.to_object(py)))
+}
+
+#[pyfunction]
+pub fn encode_secret_share_py(py: Python, x: u64, y: u128) -> PyResult<PyObject> {
+    let buf = encode_secret_share(x, y);
+    Ok(PyBytes::new(py, &buf).to_object(py))
+}
+
+#[pyfunction]
+pub fn decode_secret_share_py(buf: &[u8]) -> PyResult<(u64, u128)> {
+    decode_secret_share(buf).map_err(PyErr::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient_round_trip() {
+        let indices = vec![2u64, 5, 5000, 5001, 70000];
+        let values = vec![0.5f32, -1.25, 3.0, -0.000123, 42.0];
+        let buf = encode_compressed_gradient(100_000, &indices, &values);
+        let (original_len, got_indices, got_values) = decode_compressed_gradient(&buf).unwrap();
+        assert_eq!(original_len, 100_000);
+        assert_eq!(got_indices, indices);
+        assert_eq!(got_values, values);
+    }
+
+    #[test]
+    fn gradient_empty_round_trip() {
+        let buf = encode_compressed_gradient(10, &[], &[]);
+        let (original_len, indices, values) = decode_compressed_gradient(&buf).unwrap();
+        assert_eq!(original_len, 10);
+        assert!(indices.is_empty());
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn share_round_trip() {
+        let buf = encode_secret_share(7, u128::MAX - 1);
+        let (x, y) = decode_secret_share(&buf).unwrap();
+        assert_eq!(x, 7);
+        assert_eq!(y, u128::MAX - 1);
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(matches!(decode_compressed_gradient(&[MAGIC_GRADIENT]), Err(CodecError::Truncated)));
+        assert!(matches!(decode_secret_share(&[MAGIC_SHARE]), Err(CodecError::Truncated)));
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut buf = encode_compressed_gradient(10, &[1], &[1.0]);
+        buf[0] = 0x00;
+        assert!(matches!(decode_compressed_gradient(&buf), Err(CodecError::BadMagic)));
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let mut buf = encode_compressed_gradient(10, &[1], &[1.0]);
+        buf[1] = VERSION + 1;
+        assert!(matches!(decode_compressed_gradient(&buf), Err(CodecError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut buf = encode_compressed_gradient(10, &[1], &[1.0]);
+        buf.push(0xFF);
+        assert!(matches!(decode_compressed_gradient(&buf), Err(CodecError::TrailingBytes)));
+    }
+
+    #[test]
+    fn rejects_index_out_of_range() {
+        // original_len=1 but the only index (0) is fine; push it out of range.
+        let buf = encode_compressed_gradient(1, &[1], &[1.0]);
+        assert!(matches!(
+            decode_compressed_gradient(&buf),
+            Err(CodecError::IndexOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_count_without_aborting() {
+        // A header claiming billions of entries in a 13-byte buffer must be
+        // rejected by the length check, never handed to Vec::with_capacity.
+        let mut buf = vec![MAGIC_GRADIENT, VERSION];
+        write_varint(&mut buf, 10); // original_len
+        write_varint(&mut buf, u64::MAX); // k, wildly larger than the buffer
+        assert!(matches!(decode_compressed_gradient(&buf), Err(CodecError::Truncated)));
+    }
+}
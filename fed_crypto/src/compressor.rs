@@ -12,18 +12,63 @@
 //
 // No magic numbers are embedded; compression_ratio is provided by the caller.
 
+use crate::dp;
 use numpy::{IntoPyArray, PyArray1, PyReadonlyArrayDyn};
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList, PyTuple};
 use pyo3::types::PySequence;
+use rand::thread_rng;
 use std::cmp::Ordering;
 
 #[pyfunction]
-pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64) -> PyResult<(PyObject, PyObject, usize, PyObject)> {
+#[pyo3(signature = (arrays, compression_ratio, dp_sigma=None, clip_norm=None, quantize_bits=None, clip_range=None))]
+pub fn compress_gradients_ext(
+    py: Python,
+    arrays: &PyAny,
+    compression_ratio: f64,
+    dp_sigma: Option<f64>,
+    clip_norm: Option<f64>,
+    quantize_bits: Option<u32>,
+    clip_range: Option<f64>,
+) -> PyResult<(PyObject, PyObject, usize, PyObject)> {
     if compression_ratio <= 0.0 {
         return Err(PyTypeError::new_err("compression_ratio must be > 0.0"));
     }
+    let dp_params = match (dp_sigma, clip_norm) {
+        (Some(sigma), Some(clip)) => {
+            if sigma <= 0.0 {
+                return Err(PyTypeError::new_err("dp_sigma must be > 0.0"));
+            }
+            if clip <= 0.0 {
+                return Err(PyTypeError::new_err("clip_norm must be > 0.0"));
+            }
+            Some((sigma, clip))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(PyTypeError::new_err(
+                "dp_sigma and clip_norm must be provided together",
+            ));
+        }
+    };
+    let quantize_params = match (quantize_bits, clip_range) {
+        (Some(bits), Some(range)) => {
+            if bits < 2 {
+                return Err(PyTypeError::new_err("quantize_bits must be >= 2"));
+            }
+            if range <= 0.0 {
+                return Err(PyTypeError::new_err("clip_range must be > 0.0"));
+            }
+            Some((bits, range))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(PyTypeError::new_err(
+                "quantize_bits and clip_range must be provided together",
+            ));
+        }
+    };
 
     let mut flat: Vec<f32> = Vec::new();
     let mut shapes: Vec<Vec<usize>> = Vec::new();
@@ -81,14 +126,16 @@ pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64
 
     let original_len = flat.len();
     if original_len == 0 {
-        let empty_vals = Vec::<f32>::new().into_pyarray(py).to_object(py);
+        let (empty_vals, dtype, scale) = encode_values(py, Vec::new(), quantize_params);
         let empty_idx = Vec::<i64>::new().into_pyarray(py).to_object(py);
         let metadata = PyDict::new(py);
         metadata.set_item("compression_ratio", compression_ratio)?;
         metadata.set_item("k", 0)?;
         metadata.set_item("original_len", 0)?;
         metadata.set_item("original_shapes", PyList::empty(py))?;
-        metadata.set_item("dtype", "float32")?;
+        metadata.set_item("dtype", dtype)?;
+        set_dp_metadata(&metadata, dp_params)?;
+        set_quantize_metadata(&metadata, quantize_params, scale)?;
         return Ok((empty_vals, empty_idx, 0usize, metadata.to_object(py)));
     }
 
@@ -99,7 +146,11 @@ pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64
     }
     if k >= original_len {
         // return all entries
-        let vals_py = flat.clone().into_pyarray(py).to_object(py);
+        let mut values = flat.clone();
+        if let Some((sigma, clip)) = dp_params {
+            apply_differential_privacy(&mut values, sigma, clip);
+        }
+        let (vals_py, dtype, scale) = encode_values(py, values, quantize_params);
         let idxs: Vec<i64> = (0..original_len).map(|i| i as i64).collect();
         let idx_py = idxs.into_pyarray(py).to_object(py);
         let metadata = PyDict::new(py);
@@ -108,7 +159,9 @@ pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64
         metadata.set_item("original_len", original_len)?;
         let shapes_py = shapes_to_pylist(py, &shapes)?;
         metadata.set_item("original_shapes", shapes_py)?;
-        metadata.set_item("dtype", "float32")?;
+        metadata.set_item("dtype", dtype)?;
+        set_dp_metadata(&metadata, dp_params)?;
+        set_quantize_metadata(&metadata, quantize_params, scale)?;
         return Ok((vals_py, idx_py, original_len, metadata.to_object(py)));
     }
 
@@ -121,10 +174,13 @@ pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64
     let topk = &pairs[nth..];
     let mut indices: Vec<usize> = topk.iter().map(|(_, idx)| *idx).collect();
     indices.sort_unstable();
-    let values: Vec<f32> = indices.iter().map(|&i| flat[i]).collect();
+    let mut values: Vec<f32> = indices.iter().map(|&i| flat[i]).collect();
+    if let Some((sigma, clip)) = dp_params {
+        apply_differential_privacy(&mut values, sigma, clip);
+    }
     let indices_i64: Vec<i64> = indices.iter().map(|&i| i as i64).collect();
 
-    let vals_py = values.into_pyarray(py).to_object(py);
+    let (vals_py, dtype, scale) = encode_values(py, values, quantize_params);
     let idx_py = indices_i64.into_pyarray(py).to_object(py);
 
     let metadata = PyDict::new(py);
@@ -133,11 +189,115 @@ pub fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64
     metadata.set_item("original_len", original_len)?;
     let shapes_py = shapes_to_pylist(py, &shapes)?;
     metadata.set_item("original_shapes", shapes_py)?;
-    metadata.set_item("dtype", "float32")?;
+    metadata.set_item("dtype", dtype)?;
+    set_dp_metadata(&metadata, dp_params)?;
+    set_quantize_metadata(&metadata, quantize_params, scale)?;
 
     Ok((vals_py, idx_py, original_len, metadata.to_object(py)))
 }
 
+/// Clips `values` (viewed as a single vector) to L2 norm `clip_norm`, then
+/// adds independent exact discrete Gaussian noise N_Z(0, dp_sigma^2) to each
+/// entry. See `dp::sample_discrete_gaussian` for the sampler itself.
+fn apply_differential_privacy(values: &mut [f32], dp_sigma: f64, clip_norm: f64) {
+    let norm_sq: f64 = values.iter().map(|v| (*v as f64) * (*v as f64)).sum();
+    let norm = norm_sq.sqrt();
+    if norm > clip_norm {
+        let scale = (clip_norm / norm) as f32;
+        for v in values.iter_mut() {
+            *v *= scale;
+        }
+    }
+    let mut rng = thread_rng();
+    for v in values.iter_mut() {
+        let noise = dp::sample_discrete_gaussian(&mut rng, dp_sigma);
+        *v += noise as f32;
+    }
+}
+
+fn set_dp_metadata(metadata: &PyDict, dp_params: Option<(f64, f64)>) -> PyResult<()> {
+    if let Some((sigma, clip)) = dp_params {
+        metadata.set_item("dp_sigma", sigma)?;
+        metadata.set_item("clip_norm", clip)?;
+        metadata.set_item("dp_mechanism", "discrete_gaussian")?;
+    }
+    Ok(())
+}
+
+/// Returns the offset added to a scaled, clipped value so that it lands in
+/// the nonnegative range `[0, 2^quantize_bits)`, i.e. `2^(quantize_bits-1)`.
+fn quantize_offset(quantize_bits: u32) -> i64 {
+    1i64 << (quantize_bits - 1)
+}
+
+/// Maps each value into a fixed-point, nonnegative integer representation
+/// suitable for finite-field secure-aggregation: scale by
+/// `(2^(quantize_bits-1) - 1) / clip_range`, clip to `[-clip_range,
+/// clip_range]` first, then offset into `[0, 2^quantize_bits)`. The scale
+/// uses `offset - 1`, not `offset`, so that `+clip_range` maps to the
+/// maximum legal residue `2^quantize_bits - 1` rather than one past it.
+/// Returns the quantized values alongside the scale used, so aggregation
+/// can be undone exactly by `dequantize`.
+fn quantize(values: &[f32], quantize_bits: u32, clip_range: f64) -> (Vec<i64>, f64) {
+    let offset = quantize_offset(quantize_bits);
+    let max_residue = 2 * offset - 1;
+    let scale = ((offset - 1) as f64) / clip_range;
+    let quantized = values
+        .iter()
+        .map(|&v| {
+            let clipped = (v as f64).clamp(-clip_range, clip_range);
+            let residue = (clipped * scale).round() as i64 + offset;
+            residue.clamp(0, max_residue)
+        })
+        .collect();
+    (quantized, scale)
+}
+
+/// Encodes `values` (already top-k-selected and, if requested, DP-noised)
+/// as the Python object returned from `compress_gradients_ext`: a fixed-point
+/// integer array when `quantize_params` is set, otherwise a plain `float32`
+/// array. Returns the encoded array, the `dtype` string for the metadata
+/// dict, and the scale used for quantization (if any).
+fn encode_values(
+    py: Python,
+    values: Vec<f32>,
+    quantize_params: Option<(u32, f64)>,
+) -> (PyObject, &'static str, Option<f64>) {
+    match quantize_params {
+        Some((bits, clip_range)) => {
+            let (quantized, scale) = quantize(&values, bits, clip_range);
+            (quantized.into_pyarray(py).to_object(py), "uint_quantized", Some(scale))
+        }
+        None => (values.into_pyarray(py).to_object(py), "float32", None),
+    }
+}
+
+fn set_quantize_metadata(
+    metadata: &PyDict,
+    quantize_params: Option<(u32, f64)>,
+    scale: Option<f64>,
+) -> PyResult<()> {
+    if let (Some((bits, clip_range)), Some(scale)) = (quantize_params, scale) {
+        metadata.set_item("quantize_bits", bits)?;
+        metadata.set_item("clip_range", clip_range)?;
+        metadata.set_item("scale", scale)?;
+    }
+    Ok(())
+}
+
+/// Recovers `float32` values from the integer array produced by
+/// `compress_gradients_ext` with `quantize_bits`/`clip_range` set: undo the
+/// offset, then divide by `scale`.
+#[pyfunction]
+pub fn dequantize(py: Python, values: Vec<i64>, scale: f64, quantize_bits: u32) -> PyObject {
+    let offset = quantize_offset(quantize_bits);
+    let floats: Vec<f32> = values
+        .iter()
+        .map(|&q| (((q - offset) as f64) / scale) as f32)
+        .collect();
+    floats.into_pyarray(py).to_object(py)
+}
+
 fn shapes_to_pylist(py: Python, shapes: &Vec<Vec<usize>>) -> PyResult<PyObject> {
     let list = PyList::empty(py);
     for s in shapes.iter() {
@@ -0,0 +1,241 @@
+// fed_crypto/src/fp127.rs
+//
+// Fast modular arithmetic for the Shamir secret-sharing field
+// GF(p), p = 2^127 - 1 (a Mersenne prime). `split_secret` and
+// `reconstruct_secret_pairs` previously did every multiply/reduce in
+// `BigUint` with a full `% &p`, which dominates runtime for large `n`/`t`.
+// `Fp127` instead keeps field elements in a single `u128` limb and reduces
+// products with Mersenne folding instead of division.
+
+use std::ops::{Add, Mul, Sub};
+
+/// p = 2^127 - 1.
+pub const MODULUS: u128 = (1u128 << 127) - 1;
+
+/// An element of GF(2^127 - 1), always kept in canonical form `[0, p)`.
+///
+/// Note `2^127 - 1` itself (the all-ones 127-bit pattern) is congruent to
+/// zero mod p, so it is never a valid canonical representative; `reduce_u128`
+/// maps it to `0`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fp127(u128);
+
+impl Fp127 {
+    pub fn zero() -> Self {
+        Fp127(0)
+    }
+
+    pub fn one() -> Self {
+        Fp127(1)
+    }
+
+    /// Builds an `Fp127` from a raw `u128`, reducing it mod p first.
+    pub fn from_u128(x: u128) -> Self {
+        Fp127(reduce_u128(x))
+    }
+
+    pub fn to_u128(self) -> u128 {
+        self.0
+    }
+
+    pub fn add(self, other: Self) -> Self {
+        let sum = self.0 + other.0; // each < p < 2^127, sum < 2^128: no overflow.
+        Fp127(if sum >= MODULUS { sum - MODULUS } else { sum })
+    }
+
+    pub fn sub(self, other: Self) -> Self {
+        if self.0 >= other.0 {
+            Fp127(self.0 - other.0)
+        } else {
+            Fp127(self.0 + MODULUS - other.0)
+        }
+    }
+
+    pub fn mul(self, other: Self) -> Self {
+        let (hi, lo) = mul_wide(self.0, other.0);
+        Fp127(fold_254(hi, lo))
+    }
+
+    /// Multiplicative inverse via Fermat's little theorem: a^(p-2) mod p.
+    /// Panics on `Fp127::zero()`, which has no inverse.
+    pub fn inv(self) -> Self {
+        assert!(self.0 != 0, "Fp127::inv called on zero");
+        let mut base = self;
+        let mut result = Fp127::one();
+        let mut exp = MODULUS - 2;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(base);
+            }
+            base = base.mul(base);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl Add for Fp127 {
+    type Output = Fp127;
+    fn add(self, other: Self) -> Self {
+        Fp127::add(self, other)
+    }
+}
+
+impl Sub for Fp127 {
+    type Output = Fp127;
+    fn sub(self, other: Self) -> Self {
+        Fp127::sub(self, other)
+    }
+}
+
+impl Mul for Fp127 {
+    type Output = Fp127;
+    fn mul(self, other: Self) -> Self {
+        Fp127::mul(self, other)
+    }
+}
+
+/// Reduces an arbitrary `u128` mod p via one Mersenne fold plus a final
+/// conditional subtraction (a `u128` is at most 128 bits, i.e. at most one
+/// bit above the 127-bit modulus).
+fn reduce_u128(x: u128) -> u128 {
+    let hi = x >> 127;
+    let lo = x & MODULUS;
+    let folded = hi + lo;
+    if folded >= MODULUS {
+        folded - MODULUS
+    } else {
+        folded
+    }
+}
+
+/// Splits `a`, `b` (each `< 2^127`) into 64-bit halves and computes the
+/// 254-bit product `a * b` as `(hi, lo)` with `a * b = hi * 2^128 + lo` and
+/// `lo < 2^128`.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let a_lo = a & u64::MAX as u128;
+    let a_hi = a >> 64; // < 2^63, since a < 2^127
+    let b_lo = b & u64::MAX as u128;
+    let b_hi = b >> 64; // < 2^63, since b < 2^127
+
+    let lo_lo = a_lo * b_lo; // < 2^128
+    let lo_hi = a_lo * b_hi; // < 2^127
+    let hi_lo = a_hi * b_lo; // < 2^127
+    let hi_hi = a_hi * b_hi; // < 2^126
+
+    let mid = lo_hi + hi_lo; // < 2^128, no overflow since both < 2^127
+
+    let mid_lo = mid & u64::MAX as u128;
+    let mid_hi = mid >> 64;
+
+    let (low, carry) = lo_lo.overflowing_add(mid_lo << 64);
+    let high = hi_hi + mid_hi + (carry as u128);
+
+    (high, low)
+}
+
+/// Folds a 254-bit product `hi * 2^128 + lo` (`lo < 2^128`) down to its
+/// canonical representative mod `p = 2^127 - 1`, using `2^127 ≡ 1 (mod p)`.
+fn fold_254(hi: u128, lo: u128) -> u128 {
+    // Re-split the product at the 2^127 boundary rather than 2^128: the low
+    // limb contributes its low 127 bits plus the bit it carries into the
+    // high limb.
+    let l = lo & MODULUS;
+    let h = (hi << 1) | (lo >> 127);
+
+    // First fold: h * 2^127 + l ≡ h + l (mod p).
+    let s1 = h + l;
+
+    // s1 can still exceed p (it is at most ~2^128), so fold once more.
+    let l2 = s1 & MODULUS;
+    let h2 = s1 >> 127;
+    let s2 = h2 + l2;
+
+    if s2 >= MODULUS {
+        s2 - MODULUS
+    } else {
+        s2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    fn modulus_big() -> BigUint {
+        BigUint::from(MODULUS)
+    }
+
+    fn to_big(x: Fp127) -> BigUint {
+        BigUint::from(x.to_u128())
+    }
+
+    // A handful of fixed, hand-picked values: 0, 1, p-1 (the edges of the
+    // canonical range) plus some arbitrary interior points.
+    fn sample_values() -> Vec<u128> {
+        vec![
+            0,
+            1,
+            MODULUS - 1,
+            12345,
+            u128::from(u64::MAX),
+            MODULUS / 2,
+            MODULUS / 2 + 1,
+            0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a,
+        ]
+    }
+
+    #[test]
+    fn add_matches_biguint_oracle() {
+        let p = modulus_big();
+        for &a in &sample_values() {
+            for &b in &sample_values() {
+                let got = to_big(Fp127::from_u128(a).add(Fp127::from_u128(b)));
+                let want = (BigUint::from(a) + BigUint::from(b)) % &p;
+                assert_eq!(got, want, "a={a} b={b}");
+            }
+        }
+    }
+
+    #[test]
+    fn sub_matches_biguint_oracle() {
+        let p = modulus_big();
+        for &a in &sample_values() {
+            for &b in &sample_values() {
+                let got = to_big(Fp127::from_u128(a).sub(Fp127::from_u128(b)));
+                let want = (BigUint::from(a) + &p - BigUint::from(b)) % &p;
+                assert_eq!(got, want, "a={a} b={b}");
+            }
+        }
+    }
+
+    #[test]
+    fn mul_matches_biguint_oracle() {
+        let p = modulus_big();
+        for &a in &sample_values() {
+            for &b in &sample_values() {
+                let got = to_big(Fp127::from_u128(a).mul(Fp127::from_u128(b)));
+                let want = (BigUint::from(a) * BigUint::from(b)) % &p;
+                assert_eq!(got, want, "a={a} b={b}");
+            }
+        }
+    }
+
+    #[test]
+    fn from_u128_reduces_mod_p() {
+        assert_eq!(Fp127::from_u128(MODULUS).to_u128(), 0);
+        assert_eq!(Fp127::from_u128(MODULUS + 5).to_u128(), 5);
+    }
+
+    #[test]
+    fn inv_is_multiplicative_inverse() {
+        for &a in &sample_values() {
+            if a == 0 {
+                continue;
+            }
+            let x = Fp127::from_u128(a);
+            assert_eq!(x.mul(x.inv()), Fp127::one(), "a={a}");
+        }
+    }
+}
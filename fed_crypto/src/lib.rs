@@ -1,16 +1,46 @@
 use pyo3::prelude::*;
 
+mod codec;
 mod compressor;
-// other modules (shamir/mask) can remain as before
+mod dp;
+mod fp127;
+mod shamir;
+// mask can remain as before
 
 #[pymodule]
 fn fed_crypto(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compress_gradients_ext, m)?)?;
+    m.add_function(wrap_pyfunction!(compressor::dequantize, m)?)?;
+    m.add_function(wrap_pyfunction!(codec::encode_compressed_gradient_py, m)?)?;
+    m.add_function(wrap_pyfunction!(codec::decode_compressed_gradient_py, m)?)?;
+    m.add_function(wrap_pyfunction!(codec::encode_secret_share_py, m)?)?;
+    m.add_function(wrap_pyfunction!(codec::decode_secret_share_py, m)?)?;
+    m.add_function(wrap_pyfunction!(shamir::split_secret_py, m)?)?;
+    m.add_function(wrap_pyfunction!(shamir::reconstruct_secret_pairs_py, m)?)?;
     Ok(())
 }
 
-/// compress_gradients_ext(arrays, compression_ratio) -> (values, indices, original_len, metadata_dict)
+/// compress_gradients_ext(arrays, compression_ratio, dp_sigma=None, clip_norm=None,
+///     quantize_bits=None, clip_range=None)
+///     -> (values, indices, original_len, metadata_dict)
 #[pyfunction]
-fn compress_gradients_ext(py: Python, arrays: &PyAny, compression_ratio: f64) -> PyResult<(PyObject, PyObject, usize, PyObject)> {
-    compressor::compress_gradients_ext(py, arrays, compression_ratio)
+#[pyo3(signature = (arrays, compression_ratio, dp_sigma=None, clip_norm=None, quantize_bits=None, clip_range=None))]
+fn compress_gradients_ext(
+    py: Python,
+    arrays: &PyAny,
+    compression_ratio: f64,
+    dp_sigma: Option<f64>,
+    clip_norm: Option<f64>,
+    quantize_bits: Option<u32>,
+    clip_range: Option<f64>,
+) -> PyResult<(PyObject, PyObject, usize, PyObject)> {
+    compressor::compress_gradients_ext(
+        py,
+        arrays,
+        compression_ratio,
+        dp_sigma,
+        clip_norm,
+        quantize_bits,
+        clip_range,
+    )
 }
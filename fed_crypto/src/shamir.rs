@@ -1,58 +1,125 @@
-use num_bigint::{BigUint, RandBigInt};
-use num_traits::{One, Zero};
+use crate::fp127::{Fp127, MODULUS};
+use num_bigint::BigUint;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
 use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Draws a uniform field element below `MODULUS` (rejection sampling on
+/// 127-bit reads, to keep the distribution exact).
+fn random_fp127(rng: &mut OsRng) -> Fp127 {
+    loop {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        let candidate = u128::from_be_bytes(bytes) >> 1; // 127 random bits
+        if candidate < MODULUS {
+            return Fp127::from_u128(candidate);
+        }
+    }
+}
 
 pub fn split_secret(secret: &[u8], n: usize, t: usize) -> Vec<(u64, BigUint)> {
-    let p = BigUint::parse_bytes(b"170141183460469231731687303715884105727", 10).unwrap();
     let secret_int = BigUint::from_bytes_be(secret);
-    assert!(secret_int < p);
+    assert!(secret_int < BigUint::from(MODULUS));
+    // The assert above guarantees this fits in 16 bytes.
+    let secret_fp = Fp127::from_u128(biguint_to_u128(&secret_int).expect("secret_int < MODULUS fits in u128"));
+
     let mut rng = OsRng;
-    let mut coeffs: Vec<BigUint> = Vec::with_capacity(t);
-    coeffs.push(secret_int.clone());
+    let mut coeffs: Vec<Fp127> = Vec::with_capacity(t);
+    coeffs.push(secret_fp);
     for _ in 1..t {
-        coeffs.push(rng.gen_biguint_below(&p));
+        coeffs.push(random_fp127(&mut rng));
     }
+
     let mut res = Vec::new();
     for i in 1..=n {
-        let x = BigUint::from(i as u64);
-        let mut acc = BigUint::zero();
+        let x = Fp127::from_u128(i as u128);
+        // Horner's method, entirely in Fp127.
+        let mut acc = Fp127::zero();
         for a in coeffs.iter().rev() {
-            acc = (acc * &x + a) % &p;
+            acc = acc.mul(x).add(*a);
         }
-        res.push((i as u64, acc));
+        res.push((i as u64, u128_to_biguint(acc.to_u128())));
     }
     res
 }
 
 pub fn reconstruct_secret_pairs(pairs: &Vec<(u64, BigUint)>, secret_len: usize) -> Result<Vec<u8>, String> {
-    let p = BigUint::parse_bytes(b"170141183460469231731687303715884105727", 10).unwrap();
     if pairs.is_empty() {
         return Err("no pairs".to_string());
     }
-    let xs: Vec<BigUint> = pairs.iter().map(|(x, _)| BigUint::from(*x)).collect();
-    let ys: Vec<BigUint> = pairs.iter().map(|(_, y)| y.clone() % &p).collect();
-    let mut total = BigUint::zero();
+    let xs: Vec<Fp127> = pairs.iter().map(|(x, _)| Fp127::from_u128(*x as u128)).collect();
+    let mut ys: Vec<Fp127> = Vec::with_capacity(pairs.len());
+    for (_, y) in pairs.iter() {
+        let y_u128 = biguint_to_u128(y).ok_or_else(|| "share y value too large for Fp127".to_string())?;
+        ys.push(Fp127::from_u128(y_u128));
+    }
+
+    let mut total = Fp127::zero();
     for j in 0..xs.len() {
-        let xj = &xs[j];
-        let yj = &ys[j];
-        let mut num = BigUint::one();
-        let mut den = BigUint::one();
+        let xj = xs[j];
+        let yj = ys[j];
+        let mut num = Fp127::one();
+        let mut den = Fp127::one();
         for m in 0..xs.len() {
-            if m == j { continue; }
-            let xm = &xs[m];
-            num = (num * (&p - xm)) % &p;
-            let diff = (xj + &p - xm) % &p;
-            den = (den * diff) % &p;
+            if m == j {
+                continue;
+            }
+            let xm = xs[m];
+            num = num.mul(Fp127::zero().sub(xm));
+            let diff = xj.sub(xm);
+            den = den.mul(diff);
         }
-        // modular inverse of den
-        let inv_den = modinv::modinv_biguint(&den, &p).ok_or("no inverse")?;
-        let lj0 = (num * inv_den) % &p;
-        total = (total + (yj * lj0) % &p) % &p;
+        let inv_den = den.inv();
+        let lj0 = num.mul(inv_den);
+        total = total.add(yj.mul(lj0));
     }
-    let bytes = total.to_bytes_be();
+
+    let bytes = u128_to_biguint(total.to_u128()).to_bytes_be();
     // pad to secret_len
     let mut out = vec![0u8; secret_len];
     let start = secret_len.saturating_sub(bytes.len());
     out[start..].copy_from_slice(&bytes);
     Ok(out)
 }
+
+/// Converts a `BigUint` into a `u128`, for the `Fp127` fast path.
+/// `split_secret`/`reconstruct_secret_pairs` keep `BigUint` only at the
+/// byte-serialization boundary. Returns `None` if `x` does not fit in 16
+/// bytes, instead of panicking on attacker-supplied share values.
+fn biguint_to_u128(x: &BigUint) -> Option<u128> {
+    let bytes = x.to_bytes_be();
+    if bytes.len() > 16 {
+        return None;
+    }
+    let mut buf = [0u8; 16];
+    let start = 16 - bytes.len();
+    buf[start..].copy_from_slice(&bytes);
+    Some(u128::from_be_bytes(buf))
+}
+
+fn u128_to_biguint(x: u128) -> BigUint {
+    BigUint::from_bytes_be(&x.to_be_bytes())
+}
+
+/// `split_secret_py(secret, n, t) -> [(x, y_bytes), ...]`, with each share's `y`
+/// as big-endian bytes (the `BigUint`/`Fp127` boundary is not itself
+/// Python-representable).
+#[pyfunction]
+pub fn split_secret_py(secret: Vec<u8>, n: usize, t: usize) -> Vec<(u64, Vec<u8>)> {
+    split_secret(&secret, n, t)
+        .into_iter()
+        .map(|(x, y)| (x, y.to_bytes_be()))
+        .collect()
+}
+
+/// `reconstruct_secret_pairs_py(pairs, secret_len) -> secret`, the inverse of
+/// `split_secret_py`.
+#[pyfunction]
+pub fn reconstruct_secret_pairs_py(pairs: Vec<(u64, Vec<u8>)>, secret_len: usize) -> PyResult<Vec<u8>> {
+    let pairs: Vec<(u64, BigUint)> = pairs
+        .into_iter()
+        .map(|(x, y)| (x, BigUint::from_bytes_be(&y)))
+        .collect();
+    reconstruct_secret_pairs(&pairs, secret_len).map_err(PyValueError::new_err)
+}
@@ -1,32 +1,95 @@
-// vsa_proofs/src/circuit.rs
-use bellman::{Circuit, ConstraintSystem, SynthesisError};
-use bellman::gadgets::num::AllocatedNum;
-use bls12_381::Scalar;
-
-pub struct VsaCircuit {
-    pub x: Option<Scalar>,
-    pub y: Option<Scalar>,
-}
-
-impl Circuit<Scalar> for VsaCircuit {
-    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
-        let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
-            self.x.ok_or(SynthesisError::AssignmentMissing)
-        })?;
-
-        let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
-            self.y.ok_or(SynthesisError::AssignmentMissing)
-        })?;
-
-        // 简单约束：y = x^2
-        let x_sq = x.square(cs.namespace(|| "x^2"))?;
-        cs.enforce(
-            || "enforce y = x^2",
-            |lc| lc + x_sq.get_variable(),
-            |lc| lc + CS::one(),
-            |lc| lc + y.get_variable(),
-        );
-
-        Ok(())
-    }
-}
+// vsa_proofs/src/circuit.rs
+use bellman::{Circuit, ConstraintSystem, SynthesisError};
+use bellman::gadgets::num::AllocatedNum;
+use bls12_381::Scalar;
+use ff::Field;
+
+/// Number of bits used to decompose `bound - sumsq` into a range proof.
+///
+/// This must exceed the bit-length of the largest legal public bound `B`;
+/// otherwise `B - sumsq` can wrap around the field modulus and the prover
+/// could "prove" a negative quantity is a valid sum of `L` bits.
+pub const RANGE_BITS: usize = 128;
+
+/// Proves that a client's quantized gradient vector `grads` has squared L2
+/// norm at most the public `bound`, without revealing the gradient itself.
+///
+/// An aggregator uses this to reject poisoning / norm-inflation attacks
+/// before summing client updates.
+pub struct NormBoundCircuit {
+    pub grads: Vec<Option<Scalar>>,
+    pub bound: Option<Scalar>,
+}
+
+impl Circuit<Scalar> for NormBoundCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        // Allocate each g_i, squaring and accumulating into sumsq.
+        let mut sumsq_lc = bellman::LinearCombination::<Scalar>::zero();
+        let mut sumsq_val: Option<Scalar> = Some(Scalar::zero());
+        for (i, g) in self.grads.iter().enumerate() {
+            let g_i = AllocatedNum::alloc(cs.namespace(|| format!("g_{}", i)), || {
+                g.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let g_sq = g_i.square(cs.namespace(|| format!("g_{}^2", i)))?;
+            sumsq_lc = sumsq_lc + g_sq.get_variable();
+            sumsq_val = match (sumsq_val, g_sq.get_value()) {
+                (Some(acc), Some(v)) => Some(acc + v),
+                _ => None,
+            };
+        }
+
+        // Public input: the bound B.
+        let bound_num = AllocatedNum::alloc_input(cs.namespace(|| "bound"), || {
+            self.bound.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        // Range-prove B - sumsq >= 0 via a boolean decomposition:
+        //   sum_j b_j * 2^j = B - sumsq,  b_j * (b_j - 1) = 0.
+        let diff_val = match (self.bound, sumsq_val) {
+            (Some(b), Some(s)) => Some(b - s),
+            _ => None,
+        };
+
+        let mut bits_lc = bellman::LinearCombination::<Scalar>::zero();
+        let mut coeff = Scalar::one();
+        for j in 0..RANGE_BITS {
+            let bit_val = diff_val.map(|d| bit_at(&d, j));
+            let bit = AllocatedNum::alloc(cs.namespace(|| format!("bit_{}", j)), || {
+                bit_val.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+
+            // b_j * (b_j - 1) = 0  =>  b_j is boolean.
+            cs.enforce(
+                || format!("bit_{}_is_boolean", j),
+                |lc| lc + bit.get_variable(),
+                |lc| lc + bit.get_variable() - CS::one(),
+                |lc| lc,
+            );
+
+            bits_lc = bits_lc + (coeff, bit.get_variable());
+            coeff = coeff.double();
+        }
+
+        // sum_j b_j * 2^j = B - sumsq
+        cs.enforce(
+            || "range_decomposition",
+            |lc| lc + &bits_lc,
+            |lc| lc + CS::one(),
+            |lc| lc + bound_num.get_variable() - &sumsq_lc,
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the `j`-th least-significant bit of `scalar`'s canonical byte
+/// representation, interpreted little-endian.
+fn bit_at(scalar: &Scalar, j: usize) -> Scalar {
+    let bytes = scalar.to_bytes();
+    let byte = bytes[j / 8];
+    if (byte >> (j % 8)) & 1 == 1 {
+        Scalar::one()
+    } else {
+        Scalar::zero()
+    }
+}
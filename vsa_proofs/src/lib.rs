@@ -1,36 +1,60 @@
 // vsa_proofs/src/lib.rs
 mod circuit;
+mod solidity;
 
-use crate::circuit::VsaCircuit;
+pub use solidity::{render_solidity_verifier, render_solidity_verifier_ffi};
+
+use crate::circuit::NormBoundCircuit;
 use bls12_381::Scalar;
 use bellman::groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
 use rand::thread_rng;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
-#[no_mangle]
-pub extern "C" fn prove_and_verify(x: u64) -> bool {
+/// Runs the full norm-bound proof lifecycle for a quantized gradient vector:
+/// generates fresh Groth16 parameters for a circuit sized to `grads.len()`,
+/// proves that `sum(g_i^2) <= bound`, and immediately verifies the proof.
+///
+/// Returns `true` iff the proof both generates and verifies successfully,
+/// i.e. the gradient's squared L2 norm is at most `bound`.
+fn prove_and_verify_scalars(grads: &[u64], bound: u64) -> bool {
     let rng = &mut thread_rng();
 
-    // 构造电路: y = x^2
-    let x_scalar = Scalar::from(x);
-    let y_scalar = x_scalar * x_scalar;
+    let grad_scalars: Vec<Scalar> = grads.iter().map(|&g| Scalar::from(g)).collect();
+    let bound_scalar = Scalar::from(bound);
 
-    let circuit = VsaCircuit {
-        x: Some(x_scalar),
-        y: Some(y_scalar),
+    let setup_circuit = NormBoundCircuit {
+        grads: grad_scalars.iter().map(|s| Some(*s)).collect(),
+        bound: Some(bound_scalar),
+    };
+    let params = match generate_random_parameters::<bls12_381::Bls12, _, _>(setup_circuit, rng) {
+        Ok(p) => p,
+        Err(_) => return false,
     };
-
-    let params = generate_random_parameters::<_, _, _>(circuit, rng).unwrap();
     let pvk = prepare_verifying_key(&params.vk);
 
-    // 生成 proof
-    let circuit2 = VsaCircuit {
-        x: Some(x_scalar),
-        y: Some(y_scalar),
+    let prove_circuit = NormBoundCircuit {
+        grads: grad_scalars.iter().map(|s| Some(*s)).collect(),
+        bound: Some(bound_scalar),
     };
-    let proof = create_random_proof(circuit2, &params, rng).unwrap();
+    let proof = match create_random_proof(prove_circuit, &params, rng) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    verify_proof(&pvk, &proof, &[bound_scalar]).unwrap_or(false)
+}
 
-    // 验证 proof
-    verify_proof(&pvk, &proof, &[y_scalar]).unwrap_or(false)
+/// `grads` points to `grads_len` little-endian `u64` gradient values;
+/// `bound` is the public squared-L2-norm bound. Returns `true` iff the
+/// client's gradient proves (and verifies) to have squared norm at most
+/// `bound`, so an aggregator can reject poisoning / norm-inflation attacks
+/// without ever seeing the gradient itself.
+#[no_mangle]
+pub extern "C" fn prove_and_verify(grads: *const u64, grads_len: usize, bound: u64) -> bool {
+    if grads.is_null() {
+        return false;
+    }
+    let grads_slice = unsafe { std::slice::from_raw_parts(grads, grads_len) };
+    prove_and_verify_scalars(grads_slice, bound)
 }
@@ -0,0 +1,314 @@
+// vsa_proofs/src/solidity.rs
+//
+// Emits a standalone Solidity verifier for a Groth16 verifying key, so a
+// federated-learning coordinator can check client norm-bound proofs
+// (see `circuit::NormBoundCircuit`) on-chain instead of trusting an
+// off-chain checker.
+//
+// The generated contract hardcodes the verifying key as hex constants and
+// implements the pairing equation
+//     e(A, B) = e(alpha, beta) * e(vk_x, gamma) * e(C, delta)
+// by calling the EIP-2537 BLS12-381 precompiles: G1 addition (0x0b), G1
+// multi-scalar-multiplication (0x0c), and the pairing check (0x0f).
+//
+// BLS12-381 Fp elements are 381 bits wide, so they do not fit in a single
+// `uint256`; each coordinate is split into two words (`_a` = high 256 bits
+// of the EIP-2537-padded 64-byte value, `_b` = low 256 bits), matching how
+// real EIP-2537 verifiers lay out calldata for the precompiles.
+
+use bellman::groth16::Parameters;
+use bls12_381::{Bls12, G1Affine, G2Affine};
+
+/// The BLS12-381 base field modulus, big-endian, 48 bytes.
+const FIELD_MODULUS: [u8; 48] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// Splits a big-endian, <=48-byte field element into the two `uint256` words
+/// EIP-2537 expects: pad to the 64-byte coordinate width, then take the high
+/// 32 bytes (`_a`) and low 32 bytes (`_b`) as separate hex literals.
+fn fp_to_hi_lo(bytes: &[u8]) -> (String, String) {
+    let mut padded = vec![0u8; 64 - bytes.len()];
+    padded.extend_from_slice(bytes);
+    (to_hex(&padded[0..32]), to_hex(&padded[32..64]))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("0x");
+    for byte in bytes {
+        out.push_str(&format!("{:02x}", byte));
+    }
+    out
+}
+
+/// Encodes a G1 point as its four EIP-2537 words: `(x_a, x_b, y_a, y_b)`.
+fn g1_to_words(p: &G1Affine) -> (String, String, String, String) {
+    let uncompressed = p.to_uncompressed();
+    let (x_a, x_b) = fp_to_hi_lo(&uncompressed[0..48]);
+    let (y_a, y_b) = fp_to_hi_lo(&uncompressed[48..96]);
+    (x_a, x_b, y_a, y_b)
+}
+
+/// Encodes a G2 point as its eight EIP-2537 words:
+/// `(x0_a, x0_b, x1_a, x1_b, y0_a, y0_b, y1_a, y1_b)`.
+///
+/// `to_uncompressed` serializes each Fp2 coordinate `c0 + c1*u` as `c1 || c0`
+/// (the ZCash/IETF convention), but EIP-2537 calldata expects `c0 || c1`, so
+/// the two 48-byte halves of x and of y are swapped here.
+#[allow(clippy::type_complexity)]
+fn g2_to_words(p: &G2Affine) -> (String, String, String, String, String, String, String, String) {
+    let uncompressed = p.to_uncompressed();
+    let (x1_a, x1_b) = fp_to_hi_lo(&uncompressed[0..48]);
+    let (x0_a, x0_b) = fp_to_hi_lo(&uncompressed[48..96]);
+    let (y1_a, y1_b) = fp_to_hi_lo(&uncompressed[96..144]);
+    let (y0_a, y0_b) = fp_to_hi_lo(&uncompressed[144..192]);
+    (x0_a, x0_b, x1_a, x1_b, y0_a, y0_b, y1_a, y1_b)
+}
+
+/// Renders a self-contained Solidity contract that verifies Groth16 proofs
+/// against `params.vk`, exposing
+/// `verifyProof(uint256[4] a, uint256[8] b, uint256[4] c, uint256[] input)`,
+/// where `a`/`c` are `(x_a, x_b, y_a, y_b)` G1 points and `b` is the
+/// `(x0_a, x0_b, x1_a, x1_b, y0_a, y0_b, y1_a, y1_b)` G2 point, in the same
+/// split-word layout as the hardcoded verifying key below.
+pub fn render_solidity_verifier(params: &Parameters<Bls12>) -> String {
+    let vk = &params.vk;
+
+    let (alpha_xa, alpha_xb, alpha_ya, alpha_yb) = g1_to_words(&vk.alpha_g1);
+    let (beta_x0a, beta_x0b, beta_x1a, beta_x1b, beta_y0a, beta_y0b, beta_y1a, beta_y1b) =
+        g2_to_words(&vk.beta_g2);
+    let (gamma_x0a, gamma_x0b, gamma_x1a, gamma_x1b, gamma_y0a, gamma_y0b, gamma_y1a, gamma_y1b) =
+        g2_to_words(&vk.gamma_g2);
+    let (delta_x0a, delta_x0b, delta_x1a, delta_x1b, delta_y0a, delta_y0b, delta_y1a, delta_y1b) =
+        g2_to_words(&vk.delta_g2);
+    let (p_hi, p_lo) = fp_to_hi_lo(&FIELD_MODULUS);
+
+    let ic_entries: Vec<String> = vk
+        .ic
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let (x_a, x_b, y_a, y_b) = g1_to_words(point);
+            format!("        ic[{}] = G1Point({}, {}, {}, {});", i, x_a, x_b, y_a, y_b)
+        })
+        .collect();
+    let ic_len = vk.ic.len();
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+// Auto-generated Groth16 verifier for a NormBoundCircuit proof.
+// Do not edit by hand; regenerate via vsa_proofs::render_solidity_verifier.
+pragma solidity ^0.8.20;
+
+contract NormBoundVerifier {{
+    // EIP-2537 BLS12-381 precompiles.
+    address constant G1_ADD = 0x000000000000000000000000000000000000000b;
+    address constant G1_MSM = 0x000000000000000000000000000000000000000c;
+    address constant PAIRING_CHECK = 0x000000000000000000000000000000000000000f;
+
+    // BLS12-381 base field modulus, split into the same (hi, lo) 256-bit
+    // words used for every Fp coordinate below, for use by `fieldNeg`.
+    uint256 constant P_HI = {p_hi};
+    uint256 constant P_LO = {p_lo};
+
+    // Each Fp coordinate is padded to 64 bytes per EIP-2537 and split into
+    // a high word (`_a`) and low word (`_b`).
+    struct G1Point {{
+        uint256 x_a;
+        uint256 x_b;
+        uint256 y_a;
+        uint256 y_b;
+    }}
+
+    struct G2Point {{
+        uint256 x0_a;
+        uint256 x0_b;
+        uint256 x1_a;
+        uint256 x1_b;
+        uint256 y0_a;
+        uint256 y0_b;
+        uint256 y1_a;
+        uint256 y1_b;
+    }}
+
+    G1Point alpha_g1 = G1Point({alpha_xa}, {alpha_xb}, {alpha_ya}, {alpha_yb});
+    G2Point beta_g2 = G2Point({beta_x0a}, {beta_x0b}, {beta_x1a}, {beta_x1b}, {beta_y0a}, {beta_y0b}, {beta_y1a}, {beta_y1b});
+    G2Point gamma_g2 = G2Point({gamma_x0a}, {gamma_x0b}, {gamma_x1a}, {gamma_x1b}, {gamma_y0a}, {gamma_y0b}, {gamma_y1a}, {gamma_y1b});
+    G2Point delta_g2 = G2Point({delta_x0a}, {delta_x0b}, {delta_x1a}, {delta_x1b}, {delta_y0a}, {delta_y0b}, {delta_y1a}, {delta_y1b});
+
+    uint256 constant IC_LEN = {ic_len};
+    G1Point[IC_LEN] ic;
+
+    constructor() {{
+{ic_entries}
+    }}
+
+    /// Field negation mod P, on a coordinate split into (hi, lo) 256-bit
+    /// words; `verifyProof` uses this (via `negG2`) to negate B (equivalently, flip the
+    /// sign of one side of the pairing equation) before calling the
+    /// precompile, since the precompile checks a PRODUCT of pairings
+    /// equals one rather than an equality of two pairings.
+    function fieldNeg(uint256 hi, uint256 lo) internal pure returns (uint256 rHi, uint256 rLo) {{
+        if (hi == 0 && lo == 0) {{
+            return (0, 0);
+        }}
+        unchecked {{
+            if (lo > P_LO) {{
+                rLo = (type(uint256).max - lo) + P_LO + 1;
+                rHi = P_HI - hi - 1;
+            }} else {{
+                rLo = P_LO - lo;
+                rHi = P_HI - hi;
+            }}
+        }}
+    }}
+
+    function negG2(G2Point memory p) internal pure returns (G2Point memory r) {{
+        r.x0_a = p.x0_a;
+        r.x0_b = p.x0_b;
+        r.x1_a = p.x1_a;
+        r.x1_b = p.x1_b;
+        (r.y0_a, r.y0_b) = fieldNeg(p.y0_a, p.y0_b);
+        (r.y1_a, r.y1_b) = fieldNeg(p.y1_a, p.y1_b);
+    }}
+
+    function g1Add(G1Point memory p, G1Point memory q) internal view returns (G1Point memory r) {{
+        bytes memory input = abi.encodePacked(p.x_a, p.x_b, p.y_a, p.y_b, q.x_a, q.x_b, q.y_a, q.y_b);
+        (bool ok, bytes memory output) = G1_ADD.staticcall(input);
+        require(ok, "g1Add precompile failed");
+        (r.x_a, r.x_b, r.y_a, r.y_b) = abi.decode(output, (uint256, uint256, uint256, uint256));
+    }}
+
+    function g1Msm(G1Point[] memory points, uint256[] memory scalars) internal view returns (G1Point memory r) {{
+        bytes memory input;
+        for (uint256 i = 0; i < points.length; i++) {{
+            input = abi.encodePacked(
+                input,
+                points[i].x_a, points[i].x_b, points[i].y_a, points[i].y_b,
+                scalars[i]
+            );
+        }}
+        (bool ok, bytes memory output) = G1_MSM.staticcall(input);
+        require(ok, "g1Msm precompile failed");
+        (r.x_a, r.x_b, r.y_a, r.y_b) = abi.decode(output, (uint256, uint256, uint256, uint256));
+    }}
+
+    /// Verifies e(A, negB) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) = 1,
+    /// which (since negB = -B) is equivalent to
+    /// e(A, B) = e(alpha, beta) * e(vk_x, gamma) * e(C, delta).
+    function pairingCheck(
+        G1Point memory a,
+        G2Point memory negB,
+        G1Point memory alpha,
+        G2Point memory beta,
+        G1Point memory vkX,
+        G2Point memory gamma,
+        G1Point memory c,
+        G2Point memory delta
+    ) internal view returns (bool) {{
+        bytes memory input = abi.encodePacked(
+            a.x_a, a.x_b, a.y_a, a.y_b, negB.x0_a, negB.x0_b, negB.x1_a, negB.x1_b, negB.y0_a, negB.y0_b, negB.y1_a, negB.y1_b,
+            alpha.x_a, alpha.x_b, alpha.y_a, alpha.y_b, beta.x0_a, beta.x0_b, beta.x1_a, beta.x1_b, beta.y0_a, beta.y0_b, beta.y1_a, beta.y1_b,
+            vkX.x_a, vkX.x_b, vkX.y_a, vkX.y_b, gamma.x0_a, gamma.x0_b, gamma.x1_a, gamma.x1_b, gamma.y0_a, gamma.y0_b, gamma.y1_a, gamma.y1_b,
+            c.x_a, c.x_b, c.y_a, c.y_b, delta.x0_a, delta.x0_b, delta.x1_a, delta.x1_b, delta.y0_a, delta.y0_b, delta.y1_a, delta.y1_b
+        );
+        (bool ok, bytes memory output) = PAIRING_CHECK.staticcall(input);
+        require(ok, "pairing precompile failed");
+        return abi.decode(output, (bool));
+    }}
+
+    /// Verifies e(A,B) = e(alpha,beta) * e(vk_x,gamma) * e(C,delta), where
+    /// vk_x = ic[0] + sum_i input[i] * ic[i+1]. `a`/`c` are G1 points laid
+    /// out as `(x_a, x_b, y_a, y_b)`; `b` is a G2 point laid out as
+    /// `(x0_a, x0_b, x1_a, x1_b, y0_a, y0_b, y1_a, y1_b)`.
+    function verifyProof(
+        uint256[4] calldata a,
+        uint256[8] calldata b,
+        uint256[4] calldata c,
+        uint256[] calldata input
+    ) external view returns (bool) {{
+        require(input.length + 1 == IC_LEN, "invalid public input length");
+
+        G1Point memory vkX = ic[0];
+        if (input.length > 0) {{
+            G1Point[] memory points = new G1Point[](input.length);
+            uint256[] memory scalars = new uint256[](input.length);
+            for (uint256 i = 0; i < input.length; i++) {{
+                points[i] = ic[i + 1];
+                scalars[i] = input[i];
+            }}
+            vkX = g1Add(vkX, g1Msm(points, scalars));
+        }}
+
+        G1Point memory aPoint = G1Point(a[0], a[1], a[2], a[3]);
+        G2Point memory bPoint = G2Point(b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]);
+        G2Point memory negB = negG2(bPoint);
+        G1Point memory cPoint = G1Point(c[0], c[1], c[2], c[3]);
+
+        return pairingCheck(aPoint, negB, alpha_g1, beta_g2, vkX, gamma_g2, cPoint, delta_g2);
+    }}
+}}
+"#,
+        p_hi = p_hi,
+        p_lo = p_lo,
+        alpha_xa = alpha_xa,
+        alpha_xb = alpha_xb,
+        alpha_ya = alpha_ya,
+        alpha_yb = alpha_yb,
+        beta_x0a = beta_x0a,
+        beta_x0b = beta_x0b,
+        beta_x1a = beta_x1a,
+        beta_x1b = beta_x1b,
+        beta_y0a = beta_y0a,
+        beta_y0b = beta_y0b,
+        beta_y1a = beta_y1a,
+        beta_y1b = beta_y1b,
+        gamma_x0a = gamma_x0a,
+        gamma_x0b = gamma_x0b,
+        gamma_x1a = gamma_x1a,
+        gamma_x1b = gamma_x1b,
+        gamma_y0a = gamma_y0a,
+        gamma_y0b = gamma_y0b,
+        gamma_y1a = gamma_y1a,
+        gamma_y1b = gamma_y1b,
+        delta_x0a = delta_x0a,
+        delta_x0b = delta_x0b,
+        delta_x1a = delta_x1a,
+        delta_x1b = delta_x1b,
+        delta_y0a = delta_y0a,
+        delta_y0b = delta_y0b,
+        delta_y1a = delta_y1a,
+        delta_y1b = delta_y1b,
+        ic_len = ic_len,
+        ic_entries = ic_entries.join("\n"),
+    )
+}
+
+/// C ABI wrapper: renders the Solidity verifier for `params` and writes the
+/// UTF-8 source (NUL-terminated) into `out_buf`, which the caller must
+/// allocate with at least `out_buf_len` bytes. Returns the number of bytes
+/// written (including the terminating NUL), or 0 if `out_buf_len` is too
+/// small to hold the rendered contract.
+#[no_mangle]
+pub extern "C" fn render_solidity_verifier_ffi(
+    params: &Parameters<Bls12>,
+    out_buf: *mut u8,
+    out_buf_len: usize,
+) -> usize {
+    let rendered = render_solidity_verifier(params);
+    let c_string = match std::ffi::CString::new(rendered) {
+        Ok(s) => s,
+        Err(_) => return 0,
+    };
+    let bytes = c_string.as_bytes_with_nul();
+    if bytes.len() > out_buf_len || out_buf.is_null() {
+        return 0;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), out_buf, bytes.len());
+    }
+    bytes.len()
+}